@@ -14,22 +14,54 @@ use std::fs::File;
 use std::io;
 use std::io::Write;
 use std::path::Path;
+use std::path::PathBuf;
 use std::str::FromStr;
-use std::string::ParseError;
 
-type ConfHash = HashMap<String, ConfSection>;
+/// Ordered collection of sections.
+///
+/// Kept as a `Vec` rather than a map so that sibling sections retain the
+/// order they were parsed/created in, and so that two sections may share
+/// the same key (e.g. repeated `Host` blocks in an SSH-style file).
+type ConfSections = Vec<(String, ConfSection)>;
+
+/// Overlay `other` onto `into`.
+///
+/// Sections pair up by key *and* occurrence: the first `other` section with
+/// a given key merges onto the first `into` section with that key, the
+/// second onto the second, and so on. This keeps repeated blocks (e.g.
+/// several `Host` sections) meaningfully overlay-able instead of every
+/// duplicate clobbering the same first match. If `other` has more
+/// occurrences of a key than `into` does, the extras are appended as new
+/// sections.
+fn merge_sections(into: &mut ConfSections, other: ConfSections) {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for (key, section) in other {
+        let occurrence = seen.entry(key.clone()).or_insert(0);
+        let target = into
+            .iter_mut()
+            .filter(|(k, _)| *k == key)
+            .nth(*occurrence);
+        *occurrence += 1;
+
+        match target {
+            Some((_, existing)) => existing.merge(section),
+            None => into.push((key, section)),
+        }
+    }
+}
 
 /// Structure for Reading/Writing configuration
 #[derive(Debug, PartialEq)]
 pub struct Confindent {
-    sections: ConfHash,
+    sections: ConfSections,
 }
 
 impl Confindent {
     /// Create an empty configuration
     pub fn new() -> Self {
         Confindent {
-            sections: HashMap::new(),
+            sections: Vec::new(),
         }
     }
 
@@ -41,9 +73,53 @@ impl Confindent {
     ///
     /// let conf = Confindent::from_file("./examples/example.conf");
     /// ```
-    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let string = fs::read_to_string(path)?;
-        Ok(Confindent::from_str(&string).expect("This should not happen"))
+        Ok(Confindent::from_str(&string)?)
+    }
+
+    /// Parse each path in order and overlay the results, later paths
+    /// taking precedence over earlier ones — e.g. a system default file
+    /// followed by a user override file. Scalar values from a later file
+    /// win; nested children merge by key rather than replacing the whole
+    /// parent section. Every section is stamped with the path it came
+    /// from, retrievable via [`ConfSection::origin()`](struct.ConfSection.html#method.origin).
+    ///
+    /// ## Examples
+    /// ```
+    /// use confindent::Confindent;
+    ///
+    /// let conf = Confindent::from_files(&["./examples/defaults.conf", "./examples/user.conf"]);
+    /// ```
+    pub fn from_files<I, P>(paths: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut ret = Self::new();
+
+        for path in paths {
+            let path = path.as_ref();
+            let mut layer = Self::from_file(path)?;
+            layer.stamp_origin(path);
+            ret.merge(layer);
+        }
+
+        Ok(ret)
+    }
+
+    /// Overlay `other` onto this configuration. A top-level key present in
+    /// both merges in place (`other`'s scalar value and origin win, and
+    /// children merge recursively by key); a key only present in `other`
+    /// is appended.
+    pub fn merge(&mut self, other: Confindent) {
+        merge_sections(&mut self.sections, other.sections);
+    }
+
+    fn stamp_origin(&mut self, path: &Path) {
+        for (_, section) in &mut self.sections {
+            section.stamp_origin(path);
+        }
     }
 
     /// Writes configurtion to a file
@@ -68,28 +144,19 @@ impl Confindent {
         file.write_all(&conf.into_bytes())
     }
 
-    fn add_section(&mut self, key: String, cs: ConfSection) {
-        if self.sections.is_empty() || cs.indent_level == 0 {
-            self.sections.insert(key, cs);
-            return;
+    /// Find the `ConfSections` that a section should be inserted into,
+    /// following `path` (a chain of indices from the root down to the
+    /// immediate parent). An empty path means the root section list.
+    fn children_at_mut(&mut self, path: &[usize]) -> &mut ConfSections {
+        match path.split_first() {
+            None => &mut self.sections,
+            Some((&first, rest)) => self.sections[first].1.children_at_mut(rest),
         }
-
-        let mut hashvec: Vec<(&String, &mut ConfSection)> = self.sections.iter_mut().collect();
-        let iter = hashvec.iter_mut().rev();
-
-        for (_, sec) in iter {
-            if (**sec).indent_level == cs.indent_level - 1 {
-                (**sec).children.insert(key, cs);
-                return;
-            }
-        }
-
-        self.sections.insert(key, cs);
     }
 }
 
 impl FromStr for Confindent {
-    type Err = ParseError;
+    type Err = ConfError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut ret = Self::new();
@@ -98,12 +165,37 @@ impl FromStr for Confindent {
             return Ok(ret);
         }
 
-        let lines = s.lines();
-
-        for line in lines {
-            match ConfSection::parse(line) {
-                Some((k, v)) => ret.add_section(k, v),
-                None => continue,
+        let mut last_indent: u8 = 0;
+        let mut pending_comments: Vec<String> = Vec::new();
+
+        // Path of indices from the root down to the last section inserted,
+        // one entry per indent level. Used to find the right parent for a
+        // section nested arbitrarily deep, not just the top level.
+        let mut stack: Vec<usize> = Vec::new();
+
+        for (idx, line) in s.lines().enumerate() {
+            let line_no = idx + 1;
+
+            match ConfSection::parse(line, line_no)? {
+                None => pending_comments.clear(),
+                Some(ParsedLine::Comment(text)) => pending_comments.push(text),
+                Some(ParsedLine::Section(key, mut cs)) => {
+                    if cs.indent_level > last_indent + 1 {
+                        return Err(ConfError::IndentJump {
+                            line: line_no,
+                            from: last_indent,
+                            to: cs.indent_level,
+                        });
+                    }
+                    last_indent = cs.indent_level;
+
+                    cs.leading_comments = std::mem::take(&mut pending_comments);
+
+                    stack.truncate(cs.indent_level as usize);
+                    let children = ret.children_at_mut(&stack);
+                    children.push((key, cs));
+                    stack.push(children.len() - 1);
+                }
             }
         }
 
@@ -111,18 +203,104 @@ impl FromStr for Confindent {
     }
 }
 
+/// Errors that can occur while parsing a configuration string
+///
+/// Each variant carries the 1-based line the problem was found on. There's
+/// no column tracking: every variant here is about the shape of a whole
+/// line (its indentation, or its missing key), not a specific position
+/// within it, so a line number is enough to find the offending line.
+#[derive(Debug, PartialEq)]
+pub enum ConfError {
+    /// A section's indentation jumped by more than one level relative to
+    /// the previous section (comment lines are not counted)
+    IndentJump { line: usize, from: u8, to: u8 },
+    /// A line mixed tab and two-space indentation within its own indent run
+    MixedIndent { line: usize },
+    /// A line had indentation but no key
+    EmptyKey { line: usize },
+}
+
+impl fmt::Display for ConfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfError::IndentJump { line, from, to } => write!(
+                f,
+                "line {}: indent jumped from level {} to level {}",
+                line, from, to
+            ),
+            ConfError::MixedIndent { line } => write!(
+                f,
+                "line {}: indentation mixes tabs and spaces",
+                line
+            ),
+            ConfError::EmptyKey { line } => write!(f, "line {}: expected a key, found none", line),
+        }
+    }
+}
+
+impl std::error::Error for ConfError {}
+
+/// Error returned by [`Confindent::from_file`](struct.Confindent.html#method.from_file)
+#[derive(Debug)]
+pub enum Error {
+    /// The file could not be read
+    Io(io::Error),
+    /// The file's contents could not be parsed
+    Parse(ConfError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<ConfError> for Error {
+    fn from(e: ConfError) -> Self {
+        Error::Parse(e)
+    }
+}
+
 impl ConfParent for Confindent {
     fn get_child<T: Into<String>>(&self, key: T) -> Option<&ConfSection> {
-        self.sections.get(&key.into())
+        let key = key.into();
+        self.sections.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
     }
 
     fn get_child_mut<T: Into<String>>(&mut self, key: T) -> Option<&mut ConfSection> {
-        self.sections.get_mut(&key.into())
+        let key = key.into();
+        self.sections
+            .iter_mut()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v)
+    }
+
+    fn get_children<T: Into<String>>(&self, key: T) -> impl Iterator<Item = &ConfSection> {
+        let key = key.into();
+        self.sections
+            .iter()
+            .filter(move |(k, _)| *k == key)
+            .map(|(_, v)| v)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&String, &ConfSection)> {
+        self.sections.iter().map(|(k, v)| (k, v))
     }
 
     fn create_child<T: Into<String>>(&mut self, key: T, value: T) -> &mut Self {
-        let sec = ConfSection::new(ConfItem::parse(&value.into()), 0, HashMap::new());
-        self.sections.insert(key.into(), sec);
+        let sec = ConfSection::new(ConfItem::parse(&value.into()), 0, Vec::new());
+        self.sections.push((key.into(), sec));
 
         self
     }
@@ -144,18 +322,87 @@ impl Into<String> for Confindent {
 pub struct ConfSection {
     value: ConfItem,
     indent_level: u8,
-    children: ConfHash,
+    children: ConfSections,
+    leading_comments: Vec<String>,
+    origin: Option<PathBuf>,
 }
 
 impl ConfSection {
-    fn new(value: ConfItem, indent_level: u8, children: ConfHash) -> Self {
+    fn new(value: ConfItem, indent_level: u8, children: ConfSections) -> Self {
         ConfSection {
             value,
             indent_level,
             children,
+            leading_comments: Vec::new(),
+            origin: None,
         }
     }
 
+    /// Get the path this section was parsed from, if known (set by
+    /// [`Confindent::from_files()`](struct.Confindent.html#method.from_files))
+    pub fn origin(&self) -> Option<&Path> {
+        self.origin.as_deref()
+    }
+
+    fn stamp_origin(&mut self, path: &Path) {
+        self.origin = Some(path.to_path_buf());
+
+        for (_, child) in &mut self.children {
+            child.stamp_origin(path);
+        }
+    }
+
+    /// Overlay `other` onto this section: its value and (if set) origin
+    /// win, and its children merge recursively by key
+    fn merge(&mut self, other: ConfSection) {
+        self.value = other.value;
+
+        if let Some(origin) = other.origin {
+            self.origin = Some(origin);
+        }
+
+        merge_sections(&mut self.children, other.children);
+    }
+
+    /// See [`Confindent::children_at_mut()`](struct.Confindent.html#method.children_at_mut)
+    fn children_at_mut(&mut self, path: &[usize]) -> &mut ConfSections {
+        match path.split_first() {
+            None => &mut self.children,
+            Some((&first, rest)) => self.children[first].1.children_at_mut(rest),
+        }
+    }
+
+    /// Get the comment lines immediately preceding this section, in source order
+    ///
+    /// ## Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use confindent::{Confindent, ConfParent};
+    ///
+    /// let conf = Confindent::from_str("# a comment\nKey Value").unwrap();
+    /// assert_eq!(conf.child("Key").unwrap().comments(), &[" a comment"]);
+    /// ```
+    pub fn comments(&self) -> &[String] {
+        &self.leading_comments
+    }
+
+    /// Set the comment lines preceding this section; a multi-line string is
+    /// split into one comment line per line of input
+    ///
+    /// ## Example
+    /// ```
+    /// use confindent::{Confindent, ConfParent};
+    ///
+    /// let mut conf = Confindent::new();
+    /// conf.create("Section", "Value");
+    /// conf.child_mut("Section").unwrap().set_comment(" important setting");
+    /// ```
+    pub fn set_comment<T: Into<String>>(&mut self, comment: T) -> &mut Self {
+        self.leading_comments = comment.into().lines().map(str::to_owned).collect();
+
+        self
+    }
+
     /// Set the value of this section
     ///
     /// ## Example
@@ -228,7 +475,13 @@ impl ConfSection {
     }
 
     fn into_string(self, key: String) -> String {
-        let mut ret = format!("{} {}", key, self.value);
+        let mut ret = String::new();
+
+        for comment in &self.leading_comments {
+            ret.push_str(&format!("#{}\n", comment));
+        }
+
+        ret.push_str(&format!("{} {}", key, self.value));
 
         for (key, child) in self.children {
             let child_str = format!("\n\t{}", child.into_string(key).replace('\n', "\n\t"));
@@ -238,61 +491,143 @@ impl ConfSection {
         ret
     }
 
-    fn parse(s: &str) -> Option<(String, Self)> {
-        if s.is_empty() || s.trim_start().is_empty() {
-            return None;
-        }
-
-        let mut workable: &str = &s;
+    /// Strip leading tab/two-space indentation, validating that a single
+    /// line doesn't mix the two styles within its own indent run
+    fn strip_indent(s: &str, line_no: usize) -> Result<(u8, &str), ConfError> {
+        let mut workable = s;
 
         let mut indent_level = 0;
+        let mut indent_style: Option<IndentChar> = None;
+
         while workable.starts_with('\t') || workable.starts_with("  ") {
+            let this_style = if workable.starts_with('\t') {
+                IndentChar::Tab
+            } else {
+                IndentChar::Space
+            };
+
+            match indent_style {
+                Some(style) if style != this_style => {
+                    return Err(ConfError::MixedIndent { line: line_no })
+                }
+                _ => indent_style = Some(this_style),
+            }
+
             indent_level += 1;
 
-            let offset = if workable.starts_with('\t') { 1 } else { 2 };
+            let offset = if this_style == IndentChar::Tab { 1 } else { 2 };
 
             workable = match workable.get(offset..) {
                 Some(slice) => slice,
-                None => return None,
+                None => return Ok((indent_level, "")),
             };
         }
 
-        let split: Vec<&str> = workable.split(char::is_whitespace).collect();
+        Ok((indent_level, workable))
+    }
 
-        let key = match split.get(0) {
-            Some(key) => (*key).to_owned(),
-            None => return None,
-        };
+    /// Truncate `rest` at the first unquoted, unescaped `#`, which starts an
+    /// inline comment
+    fn split_inline_comment(rest: &str) -> &str {
+        let mut in_quotes = false;
+        let mut chars = rest.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => in_quotes = !in_quotes,
+                '#' if !in_quotes => return rest[..i].trim_end(),
+                _ => {}
+            }
+        }
+
+        rest
+    }
+
+    fn parse(s: &str, line_no: usize) -> Result<Option<ParsedLine>, ConfError> {
+        if s.is_empty() || s.trim_start().is_empty() {
+            return Ok(None);
+        }
+
+        let (indent_level, workable) = Self::strip_indent(s, line_no)?;
+
+        if let Some(comment) = workable.strip_prefix('#') {
+            return Ok(Some(ParsedLine::Comment(comment.to_owned())));
+        }
 
-        let value = match split.get(1) {
-            Some(value) => ConfItem::parse(value),
-            None => ConfItem::Empty,
+        let (key, rest) = match workable.find(char::is_whitespace) {
+            Some(idx) => (&workable[..idx], workable[idx..].trim_start()),
+            None => (workable, ""),
         };
 
-        Some((key, Self::new(value, indent_level, HashMap::new())))
+        if key.is_empty() {
+            return Err(ConfError::EmptyKey { line: line_no });
+        }
+
+        let value = ConfItem::parse_file_value(Self::split_inline_comment(rest));
+
+        Ok(Some(ParsedLine::Section(
+            key.to_owned(),
+            Self::new(value, indent_level, Vec::new()),
+        )))
     }
 }
 
+/// The result of parsing a single line of a configuration file
+#[derive(Debug, PartialEq)]
+enum ParsedLine {
+    Comment(String),
+    Section(String, ConfSection),
+}
+
 impl ConfParent for ConfSection {
     fn get_child<T: Into<String>>(&self, key: T) -> Option<&ConfSection> {
-        self.children.get(&key.into())
+        let key = key.into();
+        self.children
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v)
     }
 
     fn get_child_mut<T: Into<String>>(&mut self, key: T) -> Option<&mut ConfSection> {
-        self.children.get_mut(&key.into())
+        let key = key.into();
+        self.children
+            .iter_mut()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v)
+    }
+
+    fn get_children<T: Into<String>>(&self, key: T) -> impl Iterator<Item = &ConfSection> {
+        let key = key.into();
+        self.children
+            .iter()
+            .filter(move |(k, _)| *k == key)
+            .map(|(_, v)| v)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&String, &ConfSection)> {
+        self.children.iter().map(|(k, v)| (k, v))
     }
 
     fn create_child<T: Into<String>>(&mut self, key: T, value: T) -> &mut Self {
         let sec = ConfSection::new(
             ConfItem::parse(&value.into()),
             self.indent_level + 1,
-            HashMap::new(),
+            Vec::new(),
         );
-        self.children.insert(key.into(), sec);
+        self.children.push((key.into(), sec));
         self
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IndentChar {
+    Tab,
+    Space,
+}
+
 #[derive(Debug, PartialEq)]
 enum ConfItem {
     Empty,
@@ -304,6 +639,84 @@ impl ConfItem {
         ConfItem::Text(s.to_owned())
     }
 
+    /// Parse a value the way it appears in a config file: a surrounding
+    /// pair of double quotes is stripped and backslash escapes are decoded,
+    /// which lets a value carry leading/trailing whitespace or a literal
+    /// `#` that would otherwise be swallowed or read as a comment.
+    fn parse_file_value(raw: &str) -> Self {
+        if raw.is_empty() {
+            return ConfItem::Empty;
+        }
+
+        let unquoted = if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+            &raw[1..raw.len() - 1]
+        } else {
+            raw
+        };
+
+        ConfItem::Text(Self::unescape(unquoted))
+    }
+
+    fn unescape(s: &str) -> String {
+        let mut ret = String::with_capacity(s.len());
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                ret.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('n') => ret.push('\n'),
+                Some('t') => ret.push('\t'),
+                Some('\\') => ret.push('\\'),
+                Some(' ') => ret.push(' '),
+                Some('#') => ret.push('#'),
+                Some('"') => ret.push('"'),
+                Some(other) => {
+                    ret.push('\\');
+                    ret.push(other);
+                }
+                None => ret.push('\\'),
+            }
+        }
+
+        ret
+    }
+
+    /// Whether `s` needs quoting/escaping to round-trip through the file
+    /// format: leading/trailing whitespace, interior whitespace, a `#`, a
+    /// `"` or a `\` would otherwise be read back differently (or as a
+    /// comment, as the start/end of a quoted value, or as an escape).
+    fn needs_escaping(s: &str) -> bool {
+        s.is_empty()
+            || s.starts_with(char::is_whitespace)
+            || s.ends_with(char::is_whitespace)
+            || s.contains(char::is_whitespace)
+            || s.contains('#')
+            || s.contains('"')
+            || s.contains('\\')
+    }
+
+    fn escape(s: &str) -> String {
+        let mut ret = String::with_capacity(s.len() + 2);
+        ret.push('"');
+
+        for c in s.chars() {
+            match c {
+                '\\' => ret.push_str("\\\\"),
+                '"' => ret.push_str("\\\""),
+                '\n' => ret.push_str("\\n"),
+                '\t' => ret.push_str("\\t"),
+                _ => ret.push(c),
+            }
+        }
+
+        ret.push('"');
+        ret
+    }
+
     fn get<T: FromStr>(&self) -> Option<T> {
         match *self {
             ConfItem::Empty => None,
@@ -314,7 +727,7 @@ impl ConfItem {
 
 /// Methods for configuration sections with children
 pub trait ConfParent {
-    /// Get a reference to a child section
+    /// Get a reference to the first child section matching `key`
     ///
     /// ## Example
     /// ```
@@ -332,7 +745,7 @@ pub trait ConfParent {
         self.get_child(key)
     }
 
-    /// Get a mutable reference to a child section
+    /// Get a mutable reference to the first child section matching `key`
     ///
     /// ## Example
     /// ```
@@ -350,7 +763,40 @@ pub trait ConfParent {
         self.get_child_mut(key)
     }
 
-    /// Create a child section
+    /// Get an iterator over every child section matching `key`, in the
+    /// order they were parsed or created
+    ///
+    /// ## Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use confindent::{Confindent, ConfParent};
+    ///
+    /// let conf = Confindent::from_str("Host a\nHost b").unwrap();
+    /// let hosts: Vec<_> = conf.get_children("Host").collect();
+    /// assert_eq!(hosts.len(), 2);
+    /// ```
+    fn get_children<T: Into<String>>(&self, key: T) -> impl Iterator<Item = &ConfSection>;
+
+    /// Shorthand for [`get_children()`](#method.get_children), collected into a `Vec`
+    fn get_all<T: Into<String>>(&self, key: T) -> Vec<&ConfSection> {
+        self.get_children(key).collect()
+    }
+
+    /// Get an ordered iterator over every direct child, keyed by section name
+    ///
+    /// ## Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use confindent::{Confindent, ConfParent};
+    ///
+    /// let conf = Confindent::from_str("Key Value").unwrap();
+    /// for (key, section) in conf.iter() {
+    ///     println!("{} = {:?}", key, section.get::<String>());
+    /// }
+    /// ```
+    fn iter(&self) -> impl Iterator<Item = (&String, &ConfSection)>;
+
+    /// Create a child section, appending it after any existing children
     ///
     /// ## Example
     /// ```
@@ -396,6 +842,9 @@ impl fmt::Display for ConfItem {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             ConfItem::Empty => write!(f, ""),
+            ConfItem::Text(s) if ConfItem::needs_escaping(s) => {
+                write!(f, "{}", ConfItem::escape(s))
+            }
             ConfItem::Text(s) => write!(f, "{}", s),
         }
     }
@@ -407,18 +856,18 @@ mod tests {
 
     #[test]
     fn parse_section_empty() {
-        assert_eq!(ConfSection::parse(""), None);
+        assert_eq!(ConfSection::parse("", 1), Ok(None));
     }
 
     #[test]
     fn parse_section_onlyindent() {
-        assert_eq!(ConfSection::parse("\t"), None);
+        assert_eq!(ConfSection::parse("\t", 1), Ok(None));
     }
 
     #[test]
     fn parse_section_noindent() {
         let test_line = "Key Value";
-        let (key, section) = ConfSection::parse(test_line).unwrap();
+        let (key, section) = parse_section(test_line);
 
         assert_eq!(key, "Key");
         assert_eq!(section.value, ConfItem::Text("Value".to_string()));
@@ -429,7 +878,7 @@ mod tests {
     #[test]
     fn parse_section_indent() {
         let test_line = "\tKey Value";
-        let (key, section) = ConfSection::parse(test_line).unwrap();
+        let (key, section) = parse_section(test_line);
 
         assert_eq!(key, "Key");
         assert_eq!(section.value, ConfItem::Text("Value".to_string()));
@@ -440,7 +889,7 @@ mod tests {
     #[test]
     fn get_config_vec() {
         let test_line = "Vec 1,2,3,4";
-        let (_, section) = ConfSection::parse(test_line).unwrap();
+        let (_, section) = parse_section(test_line);
 
         assert_eq!(section.get_vec::<u8>().unwrap(), vec![1, 2, 3, 4]);
     }
@@ -450,7 +899,7 @@ mod tests {
         let test_line = "Key Value";
         let config = Confindent::from_str(test_line).unwrap();
 
-        let first_section = config.sections.get("Key").unwrap();
+        let first_section = config.get_child("Key").unwrap();
         assert_eq!(first_section.value, ConfItem::Text("Value".to_string()));
         assert_eq!(first_section.indent_level, 0);
         assert!(first_section.children.is_empty());
@@ -461,12 +910,12 @@ mod tests {
         let test_line = "Key Value\nKey2 Value2";
         let config = Confindent::from_str(test_line).unwrap();
 
-        let first_section = config.sections.get("Key").unwrap();
+        let first_section = config.get_child("Key").unwrap();
         assert_eq!(first_section.value, ConfItem::Text("Value".to_string()));
         assert_eq!(first_section.indent_level, 0);
         assert!(first_section.children.is_empty());
 
-        let second_section = config.sections.get("Key2").unwrap();
+        let second_section = config.get_child("Key2").unwrap();
         assert_eq!(second_section.value, ConfItem::Text("Value2".to_string()));
         assert_eq!(second_section.indent_level, 0);
         assert!(second_section.children.is_empty());
@@ -477,12 +926,12 @@ mod tests {
         let test_line = "Key Value\n\tChild Value2";
         let config = Confindent::from_str(test_line).unwrap();
 
-        let first_section = config.sections.get("Key").unwrap();
+        let first_section = config.get_child("Key").unwrap();
         assert_eq!(first_section.value, ConfItem::Text("Value".to_string()));
         assert_eq!(first_section.indent_level, 0);
         assert_eq!(first_section.children.len(), 1);
 
-        let second_section = first_section.children.get("Child").unwrap();
+        let second_section = first_section.get_child("Child").unwrap();
         assert_eq!(second_section.value, ConfItem::Text("Value2".to_string()));
         assert_eq!(second_section.indent_level, 1);
         assert!(second_section.children.is_empty());
@@ -502,6 +951,291 @@ mod tests {
         verify_full_parse(&config)
     }
 
+    #[test]
+    fn duplicate_keys_preserve_order() {
+        let config_string = "Host a.example.com\nHost b.example.com\nHost c.example.com";
+        let config = Confindent::from_str(config_string).expect("Failed to parse config");
+
+        let hosts = config.get_all("Host");
+        assert_eq!(hosts.len(), 3);
+        assert_eq!(hosts[0].get::<String>(), Some("a.example.com".to_string()));
+        assert_eq!(hosts[1].get::<String>(), Some("b.example.com".to_string()));
+        assert_eq!(hosts[2].get::<String>(), Some("c.example.com".to_string()));
+    }
+
+    #[test]
+    fn parse_value_keeps_rest_of_line() {
+        let test_line = "Greeting Hello there friend";
+        let (key, section) = parse_section(test_line);
+
+        assert_eq!(key, "Greeting");
+        assert_eq!(
+            section.value,
+            ConfItem::Text("Hello there friend".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_value_quoted_preserves_edge_whitespace() {
+        let test_line = "Greeting \" Hello there friend \"";
+        let (_, section) = parse_section(test_line);
+
+        assert_eq!(
+            section.value,
+            ConfItem::Text(" Hello there friend ".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_value_escapes() {
+        let test_line = r"Greeting Tab\tNewline\nHash\#Space\ End";
+        let (_, section) = parse_section(test_line);
+
+        assert_eq!(
+            section.value,
+            ConfItem::Text("Tab\tNewline\nHash#Space End".to_string())
+        );
+    }
+
+    #[test]
+    fn value_round_trips_through_display() {
+        let mut conf = Confindent::new();
+        conf.create("Greeting", "  Hello there friend  ");
+
+        let written: String = conf.into();
+        let reparsed = Confindent::from_str(&written).unwrap();
+
+        assert_eq!(
+            reparsed.child("Greeting").unwrap().get::<String>(),
+            Some("  Hello there friend  ".to_string())
+        );
+    }
+
+    #[test]
+    fn value_with_quotes_round_trips_through_display() {
+        let mut conf = Confindent::new();
+        conf.create("Key", "\"x\"");
+
+        let written: String = conf.into();
+        let reparsed = Confindent::from_str(&written).unwrap();
+
+        assert_eq!(
+            reparsed.child("Key").unwrap().get::<String>(),
+            Some("\"x\"".to_string())
+        );
+    }
+
+    #[test]
+    fn iter_preserves_parse_order() {
+        let config_string = "Zebra 1\nApple 2\nMango 3";
+        let config = Confindent::from_str(config_string).expect("Failed to parse config");
+
+        let keys: Vec<&str> = config.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["Zebra", "Apple", "Mango"]);
+    }
+
+    #[test]
+    fn indent_jump_is_rejected() {
+        let config_string = "Key Value\n\t\tChild Value2";
+        let err = Confindent::from_str(config_string).unwrap_err();
+
+        assert_eq!(
+            err,
+            ConfError::IndentJump {
+                line: 2,
+                from: 0,
+                to: 2
+            }
+        );
+    }
+
+    #[test]
+    fn mixed_indent_is_rejected() {
+        let config_string = "Key Value\n\t  Child Value2";
+        let err = Confindent::from_str(config_string).unwrap_err();
+
+        assert_eq!(err, ConfError::MixedIndent { line: 2 });
+    }
+
+    #[test]
+    fn comment_line_is_not_a_section() {
+        let config = Confindent::from_str("# just a comment").unwrap();
+        assert!(config.iter().next().is_none());
+    }
+
+    #[test]
+    fn leading_comments_attach_to_following_section() {
+        let config_string = "# first line\n#second line\nKey Value";
+        let config = Confindent::from_str(config_string).unwrap();
+
+        let section = config.child("Key").unwrap();
+        assert_eq!(section.comments(), &[" first line", "second line"]);
+    }
+
+    #[test]
+    fn blank_line_detaches_comment_from_section() {
+        let config_string = "# a comment\n\nKey Value";
+        let config = Confindent::from_str(config_string).unwrap();
+
+        let section = config.child("Key").unwrap();
+        assert!(section.comments().is_empty());
+    }
+
+    #[test]
+    fn inline_comment_is_stripped_from_value() {
+        let config_string = "Key Value # trailing note";
+        let config = Confindent::from_str(config_string).unwrap();
+
+        assert_eq!(
+            config.child("Key").unwrap().get::<String>(),
+            Some("Value".to_string())
+        );
+    }
+
+    #[test]
+    fn quoted_hash_is_not_treated_as_comment() {
+        let config_string = r#"Key "a # b""#;
+        let config = Confindent::from_str(config_string).unwrap();
+
+        assert_eq!(
+            config.child("Key").unwrap().get::<String>(),
+            Some("a # b".to_string())
+        );
+    }
+
+    #[test]
+    fn comments_round_trip_through_display() {
+        let mut conf = Confindent::new();
+        conf.create("Section", "Value");
+        conf.child_mut("Section").unwrap().set_comment(" a comment");
+
+        let written: String = conf.into();
+        let reparsed = Confindent::from_str(&written).unwrap();
+
+        assert_eq!(
+            reparsed.child("Section").unwrap().comments(),
+            &[" a comment"]
+        );
+    }
+
+    #[test]
+    fn merge_overrides_scalar_and_appends_new_keys() {
+        let mut base = Confindent::from_str("Host example.com\nIdle 600").unwrap();
+        let overlay = Confindent::from_str("Host override.com\nNewKey new").unwrap();
+
+        base.merge(overlay);
+
+        assert_eq!(
+            base.child("Host").unwrap().get::<String>(),
+            Some("override.com".to_string())
+        );
+        assert_eq!(
+            base.child("Idle").unwrap().get::<String>(),
+            Some("600".to_string())
+        );
+        assert_eq!(
+            base.child("NewKey").unwrap().get::<String>(),
+            Some("new".to_string())
+        );
+    }
+
+    #[test]
+    fn merge_recurses_into_children_instead_of_replacing() {
+        let mut base = Confindent::from_str("Host example.com\n\tUsername user").unwrap();
+        let overlay = Confindent::from_str("Host example.com\n\tPassword pass").unwrap();
+
+        base.merge(overlay);
+
+        let host = base.child("Host").unwrap();
+        assert_eq!(
+            host.child("Username").unwrap().get::<String>(),
+            Some("user".to_string())
+        );
+        assert_eq!(
+            host.child("Password").unwrap().get::<String>(),
+            Some("pass".to_string())
+        );
+    }
+
+    #[test]
+    fn merge_pairs_duplicate_keys_by_occurrence() {
+        let mut base = Confindent::from_str("Host a\nHost b").unwrap();
+        let overlay = Confindent::from_str("Host c").unwrap();
+
+        base.merge(overlay);
+
+        let hosts: Vec<_> = base
+            .get_children("Host")
+            .map(|h| h.get::<String>().unwrap())
+            .collect();
+        assert_eq!(hosts, vec!["c".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn deeply_nested_sections_attach_to_correct_parent() {
+        let config_string = "Top value\n\tMiddle value\n\t\tBottom value";
+        let config = Confindent::from_str(config_string).unwrap();
+
+        let top = config.child("Top").unwrap();
+        assert_eq!(top.children.len(), 1);
+
+        let middle = top.child("Middle").unwrap();
+        assert_eq!(middle.indent_level, 1);
+        assert_eq!(middle.children.len(), 1);
+
+        let bottom = middle.child("Bottom").unwrap();
+        assert_eq!(bottom.indent_level, 2);
+        assert_eq!(bottom.get::<String>(), Some("value".to_string()));
+    }
+
+    #[test]
+    fn sibling_subtrees_at_same_depth_stay_separate() {
+        let config_string =
+            "First value\n\tChild value\nSecond value\n\tChild value\n\t\tGrandchild value";
+        let config = Confindent::from_str(config_string).unwrap();
+
+        assert!(config.child("First").unwrap().child("Child").unwrap().child("Grandchild").is_none());
+        assert!(config
+            .child("Second")
+            .unwrap()
+            .child("Child")
+            .unwrap()
+            .child("Grandchild")
+            .is_some());
+    }
+
+    #[test]
+    fn from_files_layers_and_stamps_origin() {
+        let dir = std::env::temp_dir().join("confindent_from_files_test");
+        fs::create_dir_all(&dir).unwrap();
+
+        let defaults = dir.join("defaults.conf");
+        let user = dir.join("user.conf");
+        fs::write(&defaults, "Idle 600\nHost example.com").unwrap();
+        fs::write(&user, "Idle 300").unwrap();
+
+        let conf = Confindent::from_files([&defaults, &user]).unwrap();
+
+        assert_eq!(
+            conf.child("Idle").unwrap().get::<String>(),
+            Some("300".to_string())
+        );
+        assert_eq!(conf.child("Idle").unwrap().origin(), Some(user.as_path()));
+        assert_eq!(
+            conf.child("Host").unwrap().origin(),
+            Some(defaults.as_path())
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn parse_section(s: &str) -> (String, ConfSection) {
+        match ConfSection::parse(s, 1).unwrap().unwrap() {
+            ParsedLine::Section(key, section) => (key, section),
+            ParsedLine::Comment(_) => panic!("expected a section, got a comment"),
+        }
+    }
+
     fn verify_full_parse(config: &Confindent) {
         let host_section = config.child("Host").expect("No Host in config");
         let hostname = host_section.get();